@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use tokio::io::{split, AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+#[cfg(not(target_os = "windows"))]
+use tokio::net::UnixListener;
+
+use pueue_lib::backend::{Backend, CommandRunner};
+use pueue_lib::network::message::{Message, PtyResizeMessage, TaskStatus};
+use pueue_lib::settings::Settings;
+
+pub mod process_handler;
+pub mod web;
+
+use process_handler::{PtyHandle, SpawnedTask};
+
+/// One entry per currently running task that requested a pty, so a later `PtyResize` message
+/// can find its master fd again.
+type PtyRegistry = Arc<Mutex<HashMap<usize, PtyHandle>>>;
+
+/// All tasks this daemon has successfully spawned, in the order they were added. Tasks whose
+/// spawn failed aren't recorded. Backs `Message::Status` responses.
+pub(crate) type TaskRegistry = Arc<Mutex<Vec<TaskStatus>>>;
+
+/// The execution backend configured for each group that isn't just running tasks locally.
+/// Groups with no entry here fall back to [Backend::Local].
+type GroupRegistry = Arc<Mutex<HashMap<String, Backend>>>;
+
+/// Publishes a fresh snapshot of `TaskRegistry` every time it changes, so the web status API can
+/// push updates to connected websocket clients instead of making them poll.
+type StatusSender = tokio::sync::watch::Sender<Vec<TaskStatus>>;
+
+/// Boot the daemon: read its config, bind the local IPC transport and accept connections until
+/// the process is killed. `_test` is accepted for parity with the CLI entrypoint; this build
+/// always runs in the foreground.
+pub async fn run(config_path: Option<PathBuf>, _profile: Option<String>, _test: bool) -> Result<()> {
+    let settings = load_settings(&config_path)?;
+    let shared = settings.shared;
+
+    std::fs::write(shared.pid_path(), std::process::id().to_string())
+        .context("Failed to write the pid file")?;
+
+    let pty_registry: PtyRegistry = Arc::new(Mutex::new(HashMap::new()));
+    let task_registry: TaskRegistry = Arc::new(Mutex::new(Vec::new()));
+    let group_registry: GroupRegistry = Arc::new(Mutex::new(HashMap::new()));
+    let log_dir = shared.pueue_directory();
+    let mut next_task_id = 0usize;
+
+    let (status_tx, _status_rx) = tokio::sync::watch::channel(task_registry.lock().unwrap().clone());
+
+    if shared.web_port.is_some() && (shared.daemon_cert.is_none() || shared.daemon_key.is_none()) {
+        anyhow::bail!(
+            "The web status API (`web_port`) requires both `daemon_cert` and `daemon_key` to be \
+             configured, since it's only ever served over TLS."
+        );
+    }
+
+    if let Some(port) = shared.web_port {
+        let task_registry = task_registry.clone();
+        let status_rx = status_tx.subscribe();
+        let pueue_directory = shared.pueue_directory();
+        let shared_secret_path = shared.shared_secret_path.clone();
+        let daemon_cert = shared.daemon_cert.clone();
+        let daemon_key = shared.daemon_key.clone();
+        tokio::spawn(async move {
+            let config = web::Config {
+                port,
+                pueue_directory,
+                task_registry,
+                status_rx,
+                shared_secret_path,
+                daemon_cert,
+                daemon_key,
+            };
+            if let Err(err) = web::serve(config).await {
+                log::warn!("Web status API stopped: {err:#}");
+            }
+        });
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let socket_path = shared.unix_socket_path();
+        if socket_path.exists() {
+            std::fs::remove_file(&socket_path).ok();
+        }
+        let listener =
+            UnixListener::bind(&socket_path).context("Failed to bind the unix socket")?;
+
+        loop {
+            let (stream, _) = listener
+                .accept()
+                .await
+                .context("Failed to accept a client connection")?;
+
+            let task_id = next_task_id;
+            next_task_id += 1;
+            spawn_connection_handler(
+                stream,
+                task_id,
+                log_dir.clone(),
+                pty_registry.clone(),
+                task_registry.clone(),
+                group_registry.clone(),
+                status_tx.clone(),
+            );
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use tokio::net::windows::named_pipe::ServerOptions;
+
+        let pipe_name = shared.named_pipe_path();
+        let mut server = ServerOptions::new()
+            .first_pipe_instance(true)
+            .create(&pipe_name)
+            .context("Failed to create the named pipe")?;
+
+        loop {
+            server
+                .connect()
+                .await
+                .context("Failed to accept a client connection")?;
+
+            // Swap in a fresh pipe instance before handing the connected one off, so the next
+            // client has something to connect to while this one is still being served.
+            let connected = std::mem::replace(
+                &mut server,
+                ServerOptions::new()
+                    .create(&pipe_name)
+                    .context("Failed to create the next named pipe instance")?,
+            );
+
+            let task_id = next_task_id;
+            next_task_id += 1;
+            spawn_connection_handler(
+                connected,
+                task_id,
+                log_dir.clone(),
+                pty_registry.clone(),
+                task_registry.clone(),
+                group_registry.clone(),
+                status_tx.clone(),
+            );
+        }
+    }
+}
+
+fn spawn_connection_handler<S>(
+    stream: S,
+    task_id: usize,
+    log_dir: PathBuf,
+    pty_registry: PtyRegistry,
+    task_registry: TaskRegistry,
+    group_registry: GroupRegistry,
+    status_tx: StatusSender,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        if let Err(err) = handle_connection(
+            stream,
+            task_id,
+            &log_dir,
+            pty_registry,
+            task_registry,
+            group_registry,
+            status_tx,
+        )
+        .await
+        {
+            log::warn!("Error while handling a client connection: {err:#}");
+        }
+    });
+}
+
+fn load_settings(config_path: &Option<PathBuf>) -> Result<Settings> {
+    let path = config_path
+        .clone()
+        .context("The daemon needs an explicit config path in this build")?;
+    let content = std::fs::read_to_string(&path).context("Failed to read the config file")?;
+
+    serde_yaml::from_str(&content).context("Failed to parse the config file")
+}
+
+async fn handle_connection<S>(
+    stream: S,
+    task_id: usize,
+    log_dir: &Path,
+    pty_registry: PtyRegistry,
+    task_registry: TaskRegistry,
+    group_registry: GroupRegistry,
+    status_tx: StatusSender,
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = split(stream);
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Some(line) = lines
+        .next_line()
+        .await
+        .context("Failed to read a message from the client")?
+    {
+        let message: Message =
+            serde_json::from_str(&line).context("Failed to parse the client's message")?;
+
+        let response = handle_message(
+            message,
+            task_id,
+            log_dir,
+            &pty_registry,
+            &task_registry,
+            &group_registry,
+            &status_tx,
+        );
+
+        let mut payload =
+            serde_json::to_vec(&response).context("Failed to serialize the response")?;
+        payload.push(b'\n');
+        writer
+            .write_all(&payload)
+            .await
+            .context("Failed to send the response to the client")?;
+    }
+
+    Ok(())
+}
+
+fn handle_message(
+    message: Message,
+    task_id: usize,
+    log_dir: &Path,
+    pty_registry: &PtyRegistry,
+    task_registry: &TaskRegistry,
+    group_registry: &GroupRegistry,
+    status_tx: &StatusSender,
+) -> Message {
+    match message {
+        Message::Add(mut add) => {
+            let backend = group_registry
+                .lock()
+                .unwrap()
+                .get(&add.group)
+                .cloned()
+                .unwrap_or(Backend::Local);
+
+            add.envs
+                .insert("PUEUE_WORKER_ID".to_string(), task_id.to_string());
+            add.envs
+                .insert("PUEUE_GROUP".to_string(), add.group.clone());
+            add.command = backend.wrap_command(&add.command, &add.envs);
+
+            let log_path = log_dir.join(format!("task_{task_id}.log"));
+            let result = match process_handler::spawn_task(&add, &log_path) {
+                Ok(SpawnedTask::Pty { child, pty }) => {
+                    pty_registry.lock().unwrap().insert(task_id, pty);
+                    // Drop the registry entry as soon as the child is reaped: its master fd would
+                    // otherwise leak for the rest of the daemon's lifetime, and a `PtyResize`
+                    // arriving afterwards would signal `handle.child_pid` once the OS has
+                    // recycled it for an unrelated process.
+                    let pty_registry = pty_registry.clone();
+                    process_handler::reap_child(child, task_id, move || {
+                        pty_registry.lock().unwrap().remove(&task_id);
+                    });
+                    Message::Success(format!("Spawned task {task_id} with a pty"))
+                }
+                Ok(SpawnedTask::Piped { child }) => {
+                    process_handler::reap_child(child, task_id, || {});
+                    Message::Success(format!("Spawned task {task_id}"))
+                }
+                Err(err) => Message::Failure(format!("Failed to spawn task {task_id}: {err:#}")),
+            };
+
+            if matches!(result, Message::Success(_)) {
+                let snapshot = {
+                    let mut registry = task_registry.lock().unwrap();
+                    registry.push(TaskStatus {
+                        task_id,
+                        command: add.command,
+                        group: add.group,
+                    });
+                    registry.clone()
+                };
+                // Push the updated status to every connected web status subscriber. Unlike `send`,
+                // `send_replace` stores the snapshot even when nobody is subscribed.
+                status_tx.send_replace(snapshot);
+            }
+
+            result
+        }
+        Message::AddGroup(group) => {
+            group_registry
+                .lock()
+                .unwrap()
+                .insert(group.name.clone(), group.backend);
+            Message::Success(format!("Added group {:?}", group.name))
+        }
+        Message::PtyResize(PtyResizeMessage {
+            task_id, width, height,
+        }) => {
+            let registry = pty_registry.lock().unwrap();
+            match registry.get(&task_id) {
+                Some(pty) => match process_handler::resize_pty(pty, width, height) {
+                    Ok(()) => Message::Success(format!("Resized task {task_id}")),
+                    Err(err) => {
+                        Message::Failure(format!("Failed to resize task {task_id}: {err:#}"))
+                    }
+                },
+                None => Message::Failure(format!("No pty registered for task {task_id}")),
+            }
+        }
+        Message::Status => Message::StatusResponse(task_registry.lock().unwrap().clone()),
+        Message::StatusResponse(_) | Message::Success(_) | Message::Failure(_) => {
+            Message::Failure("The daemon doesn't expect to receive this message".to_string())
+        }
+    }
+}
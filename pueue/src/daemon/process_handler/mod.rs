@@ -0,0 +1,266 @@
+use std::fs::File;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result};
+
+use pueue_lib::network::message::AddMessage;
+
+/// A running task's pty, kept around so a later `PtyResize` message can reach it.
+#[cfg(unix)]
+pub struct PtyHandle {
+    pub master: std::os::fd::OwnedFd,
+    pub child_pid: nix::unistd::Pid,
+}
+
+#[cfg(not(unix))]
+pub struct PtyHandle;
+
+pub enum SpawnedTask {
+    Pty {
+        child: std::process::Child,
+        pty: PtyHandle,
+    },
+    Piped {
+        child: std::process::Child,
+    },
+}
+
+/// Spawn `add.command` as a child process, honouring `add.pty` and `add.stdin`, and stream its
+/// output into the task's log file at `log_path`.
+///
+/// If a pty was requested but couldn't be allocated (unsupported platform, out of ptys, ...),
+/// this falls back to plain piped stdio rather than failing the task outright.
+pub fn spawn_task(add: &AddMessage, log_path: &std::path::Path) -> Result<SpawnedTask> {
+    if add.pty {
+        match spawn_with_pty(add, log_path) {
+            Ok(spawned) => return Ok(spawned),
+            Err(err) => {
+                log::warn!("Pty allocation failed, falling back to piped stdio: {err:#}");
+            }
+        }
+    }
+
+    spawn_piped(add, log_path)
+}
+
+#[cfg(unix)]
+fn spawn_with_pty(add: &AddMessage, log_path: &std::path::Path) -> Result<SpawnedTask> {
+    use std::os::fd::AsRawFd;
+    use std::os::unix::process::CommandExt;
+
+    let pty = nix::pty::openpty(None, None).context("Failed to allocate a pty")?;
+    let slave_raw = pty.slave.as_raw_fd();
+    let master_raw = pty.master.as_raw_fd();
+
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(&add.command)
+        .current_dir(&add.path)
+        .envs(&add.envs)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    // SAFETY: only async-signal-safe calls (setsid, dup2, close) happen between fork and exec.
+    unsafe {
+        command.pre_exec(move || {
+            nix::unistd::setsid().ok();
+            for fd in 0..=2 {
+                nix::unistd::dup2(slave_raw, fd)?;
+            }
+            // The task only needs 0/1/2. Close the spare slave fd (dup2 may have left it open
+            // above 2) and the daemon's pty master, so the task can't read/write the daemon's
+            // own pty through a leaked fd.
+            if slave_raw > 2 {
+                nix::unistd::close(slave_raw)?;
+            }
+            nix::unistd::close(master_raw)?;
+            Ok(())
+        });
+    }
+
+    let child = command.spawn().context("Failed to spawn the pty child process")?;
+    // The child has its own copy of the slave now; the daemon only needs the master.
+    drop(pty.slave);
+
+    let pty_handle = PtyHandle {
+        master: pty.master,
+        child_pid: nix::unistd::Pid::from_raw(child.id() as i32),
+    };
+    stream_pty_to_log(&pty_handle.master, log_path)?;
+
+    if let Some(payload) = &add.stdin {
+        // Canonical mode only hands a line to the child once it sees a newline or an EOF marker;
+        // write the payload followed by Ctrl-D (EOT), just like a human typing into a real
+        // terminal and then pressing Ctrl-D would.
+        let mut bytes = payload.as_bytes().to_vec();
+        bytes.push(0x04);
+        // Write on a dedicated thread rather than inline: the pty's output draining thread is
+        // already running above, but a payload bigger than the pty's buffer can still block
+        // until the child reads it, and this call runs on whatever thread handled the client's
+        // `Add` message, which is a tokio executor thread we can't afford to stall.
+        std::thread::spawn(move || {
+            if let Err(err) = nix::unistd::write(master_raw, &bytes) {
+                log::warn!("Failed to write the stdin payload to the pty: {err:#}");
+            }
+        });
+    }
+
+    Ok(SpawnedTask::Pty {
+        child,
+        pty: pty_handle,
+    })
+}
+
+#[cfg(not(unix))]
+fn spawn_with_pty(_add: &AddMessage, _log_path: &std::path::Path) -> Result<SpawnedTask> {
+    anyhow::bail!("Pty allocation isn't supported on this platform")
+}
+
+fn spawn_piped(add: &AddMessage, log_path: &std::path::Path) -> Result<SpawnedTask> {
+    let mut command = Command::new("sh");
+    command
+        .arg("-c")
+        .arg(&add.command)
+        .current_dir(&add.path)
+        .envs(&add.envs)
+        .stdin(if add.stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = command.spawn().context("Failed to spawn the child process")?;
+
+    // Start draining stdout/stderr before writing stdin below. A child that fills its own
+    // stdout/stderr pipe before it's read all of stdin would otherwise deadlock the write once
+    // nothing is around yet to drain it.
+    stream_piped_to_log(&mut child, log_path)?;
+
+    if let Some(payload) = &add.stdin {
+        let mut stdin = child
+            .stdin
+            .take()
+            .context("Child process didn't have a stdin pipe")?;
+        let payload = payload.clone();
+        // Write on a dedicated thread: this call runs on whatever thread handled the client's
+        // `Add` message, which is a tokio executor thread, and a payload bigger than the pipe
+        // buffer would otherwise block it for as long as the child takes to read it.
+        std::thread::spawn(move || {
+            if let Err(err) = stdin.write_all(payload.as_bytes()) {
+                log::warn!("Failed to write the stdin payload: {err:#}");
+            }
+            // `stdin` is dropped here, closing the pipe and signalling EOF to the child.
+        });
+    }
+
+    Ok(SpawnedTask::Piped { child })
+}
+
+/// Wait on a task's child in the background so it's reaped as soon as it exits, instead of
+/// sticking around as a zombie for the rest of the daemon's lifetime. `on_exit` runs right after
+/// the child is reaped, so callers can prune any per-task state (e.g. a pty registry entry) that
+/// must not outlive the child, since its pid may be recycled by the OS once it's gone.
+pub fn reap_child(mut child: std::process::Child, task_id: usize, on_exit: impl FnOnce() + Send + 'static) {
+    std::thread::spawn(move || {
+        match child.wait() {
+            Ok(status) => log::debug!("Task {task_id} exited with {status}"),
+            Err(err) => log::warn!("Failed to wait on task {task_id}: {err:#}"),
+        }
+        on_exit();
+    });
+}
+
+/// Resize a task's pty and forward `SIGWINCH` to its child, mirroring what a real terminal does
+/// when the user's window changes size.
+#[cfg(unix)]
+pub fn resize_pty(handle: &PtyHandle, width: u16, height: u16) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let winsize = nix::pty::Winsize {
+        ws_row: height,
+        ws_col: width,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+
+    nix::ioctl_write_ptr_bad!(set_winsize, nix::libc::TIOCSWINSZ, nix::pty::Winsize);
+    unsafe { set_winsize(handle.master.as_raw_fd(), &winsize) }
+        .context("TIOCSWINSZ ioctl failed")?;
+
+    nix::sys::signal::kill(handle.child_pid, nix::sys::signal::Signal::SIGWINCH)
+        .context("Failed to forward SIGWINCH to the task")?;
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn resize_pty(_handle: &PtyHandle, _width: u16, _height: u16) -> Result<()> {
+    anyhow::bail!("Pty resizing isn't supported on this platform")
+}
+
+#[cfg(unix)]
+fn stream_pty_to_log(master: &std::os::fd::OwnedFd, log_path: &std::path::Path) -> Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let mut log_file = File::create(log_path).context("Failed to create the task log file")?;
+    let master_fd = master.as_raw_fd();
+
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 4096];
+        loop {
+            match nix::unistd::read(master_fd, &mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => {
+                    if log_file.write_all(&buf[..read]).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+fn stream_piped_to_log(child: &mut std::process::Child, log_path: &std::path::Path) -> Result<()> {
+    use std::io::Read;
+
+    let log_file = File::create(log_path).context("Failed to create the task log file")?;
+    let mut stdout = child
+        .stdout
+        .take()
+        .context("Child process didn't have a stdout pipe")?;
+    let mut stderr = child
+        .stderr
+        .take()
+        .context("Child process didn't have a stderr pipe")?;
+
+    let mut stdout_log = log_file
+        .try_clone()
+        .context("Failed to clone the log file handle")?;
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 4096];
+        while let Ok(read) = stdout.read(&mut buf) {
+            if read == 0 || stdout_log.write_all(&buf[..read]).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut stderr_log = log_file;
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 4096];
+        while let Ok(read) = stderr.read(&mut buf) {
+            if read == 0 || stderr_log.write_all(&buf[..read]).is_err() {
+                break;
+            }
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,285 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio_rustls::rustls;
+use tokio_rustls::TlsAcceptor;
+
+use pueue_lib::network::message::TaskStatus;
+
+use crate::daemon::TaskRegistry;
+
+mod frontend;
+mod websocket;
+
+/// Everything [serve] needs to run the web status API.
+pub struct Config {
+    pub port: u16,
+    pub pueue_directory: PathBuf,
+    pub task_registry: TaskRegistry,
+    /// Fires with a fresh task snapshot every time it changes, so `/ws` subscribers are pushed
+    /// updates instead of having to poll.
+    pub status_rx: watch::Receiver<Vec<TaskStatus>>,
+    pub shared_secret_path: Option<PathBuf>,
+    pub daemon_cert: Option<PathBuf>,
+    pub daemon_key: Option<PathBuf>,
+}
+
+/// Serve the web status API on `config.port` (`0` picks an ephemeral port) over TLS, using the
+/// daemon's own `daemon_cert`/`daemon_key` material, and write the port it actually bound to
+/// `web.port` inside the pueue directory, since callers that asked for an ephemeral port
+/// otherwise have no way to find out which one they got.
+///
+/// Three things are served:
+/// - `GET /status`: a single JSON snapshot of every task, for simple polling clients.
+/// - `GET /ws`: a websocket that pushes a fresh JSON snapshot every time the task registry
+///   changes, instead of making the client poll.
+/// - `GET /` (or `/index.html`): a small bundled dashboard that renders `/ws`'s pushes as a
+///   table. It's static markup, so it's served without the shared secret.
+///
+/// `/status` and `/ws` are gated on the shared secret, sent either as an `Authorization: Bearer`
+/// header or as a `?token=` query parameter; either one is enough. The query parameter exists
+/// because a browser's `WebSocket` constructor can't set an `Authorization` header.
+pub async fn serve(config: Config) -> Result<()> {
+    let Config {
+        port,
+        pueue_directory,
+        task_registry,
+        status_rx,
+        shared_secret_path,
+        daemon_cert,
+        daemon_key,
+    } = config;
+
+    let secret = read_shared_secret(shared_secret_path.as_deref())?;
+    let acceptor = build_tls_acceptor(daemon_cert.as_deref(), daemon_key.as_deref())?;
+
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .context("Failed to bind the web status listener")?;
+
+    let actual_port = listener
+        .local_addr()
+        .context("Failed to read the web listener's local address")?
+        .port();
+    std::fs::write(pueue_directory.join("web.port"), actual_port.to_string())
+        .context("Failed to record the web status API's port")?;
+
+    loop {
+        let (stream, _) = listener
+            .accept()
+            .await
+            .context("Failed to accept a web status client")?;
+
+        let task_registry = task_registry.clone();
+        let status_rx = status_rx.clone();
+        let secret = secret.clone();
+        let acceptor = acceptor.clone();
+        tokio::spawn(async move {
+            let stream = match acceptor.accept(stream).await {
+                Ok(stream) => stream,
+                Err(err) => {
+                    log::warn!("Web status TLS handshake failed: {err:#}");
+                    return;
+                }
+            };
+
+            if let Err(err) = respond(stream, &task_registry, status_rx, secret.as_deref()).await
+            {
+                log::warn!("Error while serving a web status request: {err:#}");
+            }
+        });
+    }
+}
+
+/// Read the daemon's shared secret from `path`, if the daemon was configured with one. Returns
+/// `None` if it wasn't, in which case the web status API is served unauthenticated.
+fn read_shared_secret(path: Option<&std::path::Path>) -> Result<Option<String>> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+
+    let secret = std::fs::read_to_string(path)
+        .context("Failed to read the web status API's shared secret file")?;
+    Ok(Some(secret.trim().to_string()))
+}
+
+/// Build a TLS acceptor from the daemon's `daemon_cert`/`daemon_key` PEM files.
+fn build_tls_acceptor(
+    cert_path: Option<&std::path::Path>,
+    key_path: Option<&std::path::Path>,
+) -> Result<TlsAcceptor> {
+    let cert_path = cert_path.context("The web status API needs `daemon_cert` to serve TLS")?;
+    let key_path = key_path.context("The web status API needs `daemon_key` to serve TLS")?;
+
+    let cert_file = std::fs::read(cert_path).context("Failed to read the daemon's TLS cert")?;
+    let key_file = std::fs::read(key_path).context("Failed to read the daemon's TLS key")?;
+
+    let certs = rustls_pemfile::certs(&mut cert_file.as_slice())
+        .context("Failed to parse the daemon's TLS cert")?
+        .into_iter()
+        .map(rustls::Certificate)
+        .collect();
+
+    let mut keys = rustls_pemfile::pkcs8_private_keys(&mut key_file.as_slice())
+        .context("Failed to parse the daemon's TLS key")?;
+    let key = rustls::PrivateKey(
+        keys.pop()
+            .context("The daemon's TLS key file didn't contain a PKCS#8 private key")?,
+    );
+
+    let server_config = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("Failed to build the daemon's TLS config")?;
+
+    Ok(TlsAcceptor::from(Arc::new(server_config)))
+}
+
+async fn respond<S>(
+    mut stream: S,
+    task_registry: &TaskRegistry,
+    status_rx: watch::Receiver<Vec<TaskStatus>>,
+    secret: Option<&str>,
+) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let request = read_request_headers(&mut stream).await?;
+    let (method, path) = request_line(&request);
+    let (path, token) = split_query_token(path);
+
+    let authorized = match secret {
+        Some(secret) => {
+            request_carries_secret(&request, secret)
+                || token.is_some_and(|token| constant_time_eq(token.as_bytes(), secret.as_bytes()))
+        }
+        None => true,
+    };
+
+    if method != "GET" {
+        return write_response(&mut stream, 404, "Not Found", "text/plain", b"Not found\n").await;
+    }
+
+    match path {
+        "/ws" => {
+            if !authorized {
+                return write_response(&mut stream, 401, "Unauthorized", "text/plain", b"Unauthorized\n").await;
+            }
+            websocket::serve(stream, &request, status_rx).await
+        }
+        "/status" => {
+            if !authorized {
+                return write_response(&mut stream, 401, "Unauthorized", "text/plain", b"Unauthorized\n").await;
+            }
+            let tasks: Vec<TaskStatus> = task_registry.lock().unwrap().clone();
+            let body = serde_json::to_vec(&tasks).context("Failed to serialize the task status")?;
+            write_response(&mut stream, 200, "OK", "application/json", &body).await
+        }
+        "/" | "/index.html" => {
+            write_response(&mut stream, 200, "OK", "text/html", frontend::INDEX_HTML.as_bytes())
+                .await
+        }
+        _ => write_response(&mut stream, 404, "Not Found", "text/plain", b"Not found\n").await,
+    }
+}
+
+/// Read until the blank line that ends the HTTP header block (or the stream closes, or the
+/// headers exceed a generous size limit), instead of trusting that a single `read()` call
+/// captures every header in one TCP segment.
+async fn read_request_headers<S: AsyncRead + Unpin>(stream: &mut S) -> Result<String> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 512];
+
+    loop {
+        if buf.windows(4).any(|window| window == b"\r\n\r\n") || buf.len() > 16 * 1024 {
+            break;
+        }
+
+        let read = stream
+            .read(&mut chunk)
+            .await
+            .context("Failed to read the web status request")?;
+        if read == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    }
+
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+/// Split the request's first line into its method and path, e.g. `("GET", "/status")`.
+fn request_line(request: &str) -> (&str, &str) {
+    let line = request.lines().next().unwrap_or("");
+    let mut parts = line.split_whitespace();
+    (parts.next().unwrap_or(""), parts.next().unwrap_or("/"))
+}
+
+/// Split `path` into its path and an optional `token` query parameter, e.g.
+/// `/ws?token=abc` -> `("/ws", Some("abc"))`.
+fn split_query_token(path: &str) -> (&str, Option<String>) {
+    let Some((path, query)) = path.split_once('?') else {
+        return (path, None);
+    };
+
+    let token = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "token")
+        .map(|(_, value)| value.to_string());
+
+    (path, token)
+}
+
+/// `true` if `request`'s headers carry an `Authorization: Bearer <secret>` line matching `secret`,
+/// compared in constant time so a client can't recover the secret by timing repeated guesses.
+fn request_carries_secret(request: &str, secret: &str) -> bool {
+    request
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .and_then(|(_, value)| value.trim().strip_prefix("Bearer "))
+        .is_some_and(|token| constant_time_eq(token.as_bytes(), secret.as_bytes()))
+}
+
+/// Compare two byte strings for equality without short-circuiting on the first mismatch, so the
+/// time taken doesn't leak how many leading bytes a guess got right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn write_response<S>(
+    stream: &mut S,
+    status: u16,
+    reason: &str,
+    content_type: &str,
+    body: &[u8],
+) -> Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    let headers = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+
+    stream
+        .write_all(headers.as_bytes())
+        .await
+        .context("Failed to write the HTTP response headers")?;
+    stream
+        .write_all(body)
+        .await
+        .context("Failed to write the HTTP response body")?;
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+/// A minimal, dependency-free dashboard that connects to `/ws` and renders whatever task
+/// snapshot it's pushed as a table, updating live as tasks are added. The daemon's shared secret
+/// (if any) is typed in once and kept only in page memory, then passed to `/ws` as a `?token=`
+/// query parameter, since a browser's `WebSocket` constructor can't set an `Authorization` header.
+pub const INDEX_HTML: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>pueue status</title>
+<style>
+  body { font-family: monospace; margin: 2rem; }
+  table { border-collapse: collapse; width: 100%; }
+  th, td { border: 1px solid #ccc; padding: 0.3rem 0.6rem; text-align: left; }
+  #status { color: #666; margin-bottom: 1rem; }
+</style>
+</head>
+<body>
+<h1>pueue status</h1>
+<div id="status">connecting...</div>
+<table id="tasks">
+  <thead><tr><th>task id</th><th>group</th><th>command</th></tr></thead>
+  <tbody></tbody>
+</table>
+<script>
+  const params = new URLSearchParams(location.search);
+  let token = params.get("token") || window.prompt("Shared secret (leave blank if none):") || "";
+
+  function connect() {
+    const url = new URL("/ws", location.href);
+    url.protocol = url.protocol.replace("http", "ws");
+    if (token) url.searchParams.set("token", token);
+
+    const ws = new WebSocket(url);
+    const statusEl = document.getElementById("status");
+    const body = document.querySelector("#tasks tbody");
+
+    ws.onopen = () => { statusEl.textContent = "connected"; };
+    ws.onclose = () => {
+      statusEl.textContent = "disconnected, retrying...";
+      setTimeout(connect, 1000);
+    };
+    ws.onerror = () => ws.close();
+    ws.onmessage = (event) => {
+      const tasks = JSON.parse(event.data);
+      body.innerHTML = "";
+      for (const task of tasks) {
+        const row = document.createElement("tr");
+        for (const value of [task.task_id, task.group, task.command]) {
+          const cell = document.createElement("td");
+          cell.textContent = value;
+          row.appendChild(cell);
+        }
+        body.appendChild(row);
+      }
+    };
+  }
+
+  connect();
+</script>
+</body>
+</html>
+"##;
@@ -0,0 +1,101 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use sha1::{Digest, Sha1};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::watch;
+
+use pueue_lib::network::message::TaskStatus;
+
+/// The magic string every websocket server concatenates onto the client's `Sec-WebSocket-Key`
+/// before hashing it, per RFC 6455.
+const WEBSOCKET_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Upgrade `stream` to a websocket and push a fresh JSON task snapshot every time `status_rx`
+/// changes, until the client disconnects.
+pub async fn serve<S>(mut stream: S, request: &str, mut status_rx: watch::Receiver<Vec<TaskStatus>>) -> Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let key = request
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("sec-websocket-key"))
+        .map(|(_, value)| value.trim().to_string())
+        .context("Websocket upgrade request was missing Sec-WebSocket-Key")?;
+
+    let accept = websocket_accept(&key);
+    let response = format!(
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {accept}\r\n\r\n"
+    );
+    stream
+        .write_all(response.as_bytes())
+        .await
+        .context("Failed to write the websocket handshake response")?;
+
+    // Push the current snapshot right away, then again every time it changes, until the client
+    // goes away. We don't need to read anything the client sends (it never sends us anything
+    // meaningful), just notice when it closes the connection.
+    let mut client_buf = [0u8; 256];
+    loop {
+        let snapshot = status_rx.borrow().clone();
+        let body = serde_json::to_vec(&snapshot).context("Failed to serialize the task status")?;
+        if write_text_frame(&mut stream, &body).await.is_err() {
+            break;
+        }
+
+        let keep_going = loop {
+            tokio::select! {
+                changed = status_rx.changed() => break changed.is_ok(),
+                read = stream.read(&mut client_buf) => {
+                    match read {
+                        Ok(0) | Err(_) => break false,
+                        // A close frame or any other client frame; this endpoint is push-only,
+                        // so just ignore the bytes and keep waiting until the snapshot changes
+                        // or the stream actually closes.
+                        Ok(_) => {}
+                    }
+                }
+            }
+        };
+        if !keep_going {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Compute the `Sec-WebSocket-Accept` header value for a client's `Sec-WebSocket-Key`.
+fn websocket_accept(key: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(key.as_bytes());
+    hasher.update(WEBSOCKET_GUID.as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(hasher.finalize())
+}
+
+/// Write `payload` as a single unmasked, final text frame. Servers never mask frames they send to
+/// clients (only clients masking frames to servers is required by RFC 6455).
+async fn write_text_frame<S: AsyncWrite + Unpin>(stream: &mut S, payload: &[u8]) -> Result<()> {
+    let mut frame = Vec::with_capacity(payload.len() + 10);
+    frame.push(0x81); // FIN + text opcode
+
+    let len = payload.len();
+    if len < 126 {
+        frame.push(len as u8);
+    } else if len <= u16::MAX as usize {
+        frame.push(126);
+        frame.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        frame.push(127);
+        frame.extend_from_slice(&(len as u64).to_be_bytes());
+    }
+    frame.extend_from_slice(payload);
+
+    stream
+        .write_all(&frame)
+        .await
+        .context("Failed to write a websocket frame")
+}
@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+/// Parsed command line arguments for the `pueued` binary.
+struct Args {
+    config: Option<PathBuf>,
+    profile: Option<String>,
+    verbosity: u8,
+}
+
+fn parse_args() -> Args {
+    let mut args = Args {
+        config: None,
+        profile: None,
+        verbosity: 0,
+    };
+
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--config" => args.config = iter.next().map(PathBuf::from),
+            "--profile" => args.profile = iter.next(),
+            flag if flag.starts_with('-') && flag.chars().skip(1).all(|c| c == 'v') => {
+                args.verbosity += (flag.len() - 1) as u8;
+            }
+            _ => {}
+        }
+    }
+
+    args
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = parse_args();
+
+    let level = match args.verbosity {
+        0 => log::LevelFilter::Warn,
+        1 => log::LevelFilter::Info,
+        2 => log::LevelFilter::Debug,
+        _ => log::LevelFilter::Trace,
+    };
+    env_logger::Builder::new().filter_level(level).init();
+
+    pueue::daemon::run(args.config, args.profile, false).await
+}
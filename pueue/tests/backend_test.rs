@@ -0,0 +1,66 @@
+mod helper;
+
+use helper::*;
+
+/// A task added to a group bound to a custom execution backend should actually run through
+/// that backend's wrapped command, not as a plain local child process.
+#[tokio::test]
+async fn task_lands_in_the_configured_backend() {
+    let daemon = daemon().await.unwrap();
+    let shared = &daemon.settings.shared;
+
+    create_test_group_with_backend(shared, "templated", "echo BACKEND_USED; {command}")
+        .await
+        .unwrap();
+
+    add_task_to_group(shared, "true", "templated").await.unwrap();
+
+    wait_for_log_containing(&daemon, "BACKEND_USED")
+        .await
+        .unwrap();
+}
+
+/// A command with shell metacharacters (`&&`, `;`) must stay intact once it crosses into a
+/// nested shell, instead of being parsed apart by the *local* `sh -c` that invokes the backend
+/// template. `"sh -c {command}"` stands in for what `ssh`/`docker exec` do: hand the (quoted)
+/// command off to a second shell on the other side of a boundary.
+#[tokio::test]
+async fn backend_preserves_shell_metacharacters_across_the_hop() {
+    let daemon = daemon().await.unwrap();
+    let shared = &daemon.settings.shared;
+
+    create_test_group_with_backend(shared, "templated", "sh -c {command}")
+        .await
+        .unwrap();
+
+    add_task_to_group(shared, "echo FIRST && echo SECOND; echo THIRD", "templated")
+        .await
+        .unwrap();
+
+    wait_for_log_containing(&daemon, "FIRST").await.unwrap();
+    wait_for_log_containing(&daemon, "SECOND").await.unwrap();
+    wait_for_log_containing(&daemon, "THIRD").await.unwrap();
+}
+
+/// `PUEUE_WORKER_ID`/`PUEUE_GROUP` must reach the task even when it runs in a shell that doesn't
+/// inherit the daemon's own env (as a remote `ssh`/`docker exec` session wouldn't). `"env -i sh -c
+/// {command}"` clears the env before running the nested shell, so the vars can only show up if
+/// the backend spliced them into the command text itself.
+#[tokio::test]
+async fn backend_forwards_worker_env_vars_across_the_hop() {
+    let daemon = daemon().await.unwrap();
+    let shared = &daemon.settings.shared;
+
+    create_test_group_with_backend(shared, "templated", "env -i sh -c {command}")
+        .await
+        .unwrap();
+
+    add_env_task_to_group(shared, "true", "templated")
+        .await
+        .unwrap();
+
+    wait_for_log_containing(&daemon, "WORKER_ID:").await.unwrap();
+    wait_for_log_containing(&daemon, "GROUP: templated")
+        .await
+        .unwrap();
+}
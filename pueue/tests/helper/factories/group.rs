@@ -0,0 +1,39 @@
+use anyhow::{Context, Result};
+
+use pueue_lib::backend::Backend;
+use pueue_lib::network::message::{AddGroupMessage, Message};
+use pueue_lib::settings::Shared;
+
+use crate::helper::*;
+
+/// Create a group on the test daemon that's configured to run its tasks through `backend`
+/// instead of as local child processes. `backend` is one of `"local"`, `"ssh:<host>"`,
+/// `"docker:<container>"`, or a raw template containing a `{command}` placeholder.
+pub async fn add_group_with_backend(shared: &Shared, group: &str, backend: &str) -> Result<Message> {
+    let message = AddGroupMessage {
+        name: group.to_string(),
+        backend: parse_backend(backend),
+    };
+
+    send_message(shared, message)
+        .await
+        .context("Failed to add group with backend.")
+}
+
+fn parse_backend(backend: &str) -> Backend {
+    if let Some(host) = backend.strip_prefix("ssh:") {
+        Backend::Ssh {
+            host: host.to_string(),
+        }
+    } else if let Some(container) = backend.strip_prefix("docker:") {
+        Backend::Docker {
+            container: container.to_string(),
+        }
+    } else if backend == "local" {
+        Backend::Local
+    } else {
+        Backend::Template {
+            template: backend.to_string(),
+        }
+    }
+}
@@ -0,0 +1,5 @@
+pub mod group;
+pub mod task;
+
+pub use group::*;
+pub use task::*;
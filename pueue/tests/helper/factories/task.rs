@@ -37,3 +37,38 @@ pub async fn add_env_task_to_group(shared: &Shared, command: &str, group: &str)
     let command = format!("echo WORKER_ID: $PUEUE_WORKER_ID; echo GROUP: $PUEUE_GROUP; {command}");
     add_task_to_group(shared, &command, group).await
 }
+
+/// Mini wrapper around add_task, which requests a pty for the spawned command.
+/// Useful for asserting that a task which calls `tty`/`isatty` actually reports a terminal.
+pub async fn add_pty_task(shared: &Shared, command: &str) -> Result<Message> {
+    let mut message = create_add_message(shared, command);
+    message.pty = true;
+
+    send_message(shared, message)
+        .await
+        .context("Failed to add pty task.")
+}
+
+/// Mini wrapper around add_task, which feeds `stdin` to the spawned command.
+/// Useful for driving interactive-but-scriptable tools without wrapping them in a shell.
+pub async fn add_task_with_stdin(shared: &Shared, command: &str, stdin: &str) -> Result<Message> {
+    let mut message = create_add_message(shared, command);
+    message.stdin = Some(stdin.to_string());
+
+    send_message(shared, message)
+        .await
+        .context("Failed to add task with stdin.")
+}
+
+/// Mini wrapper around add_task, which requests a pty *and* feeds `stdin` to the spawned
+/// command. Useful for asserting that a stdin payload reaches a task even when it's run
+/// through a pty rather than plain piped stdio.
+pub async fn add_pty_task_with_stdin(shared: &Shared, command: &str, stdin: &str) -> Result<Message> {
+    let mut message = create_add_message(shared, command);
+    message.pty = true;
+    message.stdin = Some(stdin.to_string());
+
+    send_message(shared, message)
+        .await
+        .context("Failed to add pty task with stdin.")
+}
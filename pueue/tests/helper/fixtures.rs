@@ -11,6 +11,7 @@ use tempfile::{Builder, TempDir};
 use tokio::io::{self, AsyncWriteExt};
 
 use pueue::daemon::run;
+use pueue_lib::manager::Manager;
 use pueue_lib::settings::*;
 
 use crate::helper::*;
@@ -32,6 +33,23 @@ pub async fn daemon() -> Result<PueueDaemon> {
     daemon_with_settings(settings, tempdir).await
 }
 
+/// Spawn `count` standalone test daemons and wire up a [Manager] that knows about all of them,
+/// named `daemon-0`, `daemon-1`, etc. Useful for integration tests that need to assert behaviour
+/// across multiple daemons, e.g. that `status` merges task lists from several of them.
+pub async fn daemons(count: usize) -> Result<(Vec<PueueDaemon>, Manager)> {
+    let mut instances = Vec::with_capacity(count);
+    let mut manager = Manager::new();
+
+    for index in 0..count {
+        let (settings, tempdir) = daemon_base_setup()?;
+        let daemon = daemon_with_settings(settings, tempdir).await?;
+        manager.add_daemon(format!("daemon-{index}"), daemon.settings.shared.clone());
+        instances.push(daemon);
+    }
+
+    Ok((instances, manager))
+}
+
 /// A helper function which takes a Pueue config, a temporary directory and spawns
 /// a daemon into the async tokio runtime.
 pub async fn daemon_with_settings(settings: Settings, tempdir: TempDir) -> Result<PueueDaemon> {
@@ -43,16 +61,39 @@ pub async fn daemon_with_settings(settings: Settings, tempdir: TempDir) -> Resul
     let path = pueue_dir.to_path_buf();
     // Start/spin off the daemon and get its PID
     tokio::spawn(run_and_handle_error(path, true));
-    let pid = get_pid(&settings.shared.pid_path()).await?;
 
     let tries = 20;
     let mut current_try = 0;
 
-    // Wait up to 1s for the unix socket to pop up.
-    let socket_path = settings.shared.unix_socket_path();
+    // Wait up to 1s for the daemon to write its pid file, instead of assuming the spawned task
+    // above already got a chance to run by the time we ask for it.
+    let pid = loop {
+        match get_pid(&settings.shared.pid_path()).await {
+            Ok(pid) => break pid,
+            Err(_) if current_try < tries => {
+                sleep_ms(50).await;
+                current_try += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    };
+
+    let mut current_try = 0;
+
+    // Wait up to 1s for the local IPC transport to pop up.
+    // On Unix this is the unix socket, on Windows the named pipe.
+    #[cfg(not(target_os = "windows"))]
+    let transport_ready = || settings.shared.unix_socket_path().exists();
+    #[cfg(target_os = "windows")]
+    let transport_ready = || {
+        tokio::net::windows::named_pipe::ClientOptions::new()
+            .open(settings.shared.named_pipe_path())
+            .is_ok()
+    };
+
     while current_try < tries {
         sleep_ms(50).await;
-        if socket_path.exists() {
+        if transport_ready() {
             create_test_groups(&settings.shared).await?;
             return Ok(PueueDaemon {
                 settings,
@@ -103,11 +144,20 @@ pub async fn standalone_daemon(shared: &Shared) -> Result<Child> {
     let tries = 20;
     let mut current_try = 0;
 
-    // Wait up to 1s for the unix socket to pop up.
-    let socket_path = shared.unix_socket_path();
+    // Wait up to 1s for the local IPC transport to pop up.
+    // On Unix this is the unix socket, on Windows the named pipe.
+    #[cfg(not(target_os = "windows"))]
+    let transport_ready = || shared.unix_socket_path().exists();
+    #[cfg(target_os = "windows")]
+    let transport_ready = || {
+        tokio::net::windows::named_pipe::ClientOptions::new()
+            .open(shared.named_pipe_path())
+            .is_ok()
+    };
+
     while current_try < tries {
         sleep_ms(50).await;
-        if socket_path.exists() {
+        if transport_ready() {
             return Ok(child);
         }
 
@@ -135,6 +185,17 @@ pub fn daemon_base_setup() -> Result<(Settings, TempDir)> {
 
     std::fs::create_dir(tempdir_path.join("certs")).unwrap();
 
+    // The web status API gates its task data (`/status`, `/ws`) on this secret; write one so tests can
+    // authenticate against it instead of disabling the check.
+    let shared_secret_path = tempdir_path.join("secret");
+    std::fs::write(&shared_secret_path, "pueue-test-secret")
+        .context("Failed to write the test shared secret file")?;
+
+    let daemon_cert = tempdir_path.join("certs").join("daemon.cert");
+    let daemon_key = tempdir_path.join("certs").join("daemon.key");
+    write_self_signed_cert(&daemon_cert, &daemon_key)
+        .context("Failed to generate a self-signed TLS cert for the web status API")?;
+
     let shared = Shared {
         pueue_directory: Some(tempdir_path.to_path_buf()),
         runtime_directory: Some(tempdir_path.to_path_buf()),
@@ -143,12 +204,17 @@ pub fn daemon_base_setup() -> Result<(Settings, TempDir)> {
         use_unix_socket: true,
         #[cfg(not(target_os = "windows"))]
         unix_socket_path: None,
+        #[cfg(target_os = "windows")]
+        named_pipe_path: None,
         pid_path: None,
         host: "localhost".to_string(),
         port: "51230".to_string(),
-        daemon_cert: Some(tempdir_path.join("certs").join("daemon.cert")),
-        daemon_key: Some(tempdir_path.join("certs").join("daemon.key")),
-        shared_secret_path: Some(tempdir_path.join("secret")),
+        daemon_cert: Some(daemon_cert),
+        daemon_key: Some(daemon_key),
+        shared_secret_path: Some(shared_secret_path),
+        // Bind the read-only HTTP/WebSocket status API to an ephemeral port, so tests can
+        // assert that the JSON it serves matches what `send_message` returns over the socket.
+        web_port: Some(0),
     };
 
     let client = Client {
@@ -185,14 +251,127 @@ pub fn daemon_base_setup() -> Result<(Settings, TempDir)> {
     Ok((settings, tempdir))
 }
 
-/// Create a few test groups that have various parallel task settings.
+/// Generate a self-signed TLS cert/key pair for the web status API to serve, since the daemon
+/// always speaks TLS there and tests don't have a real cert authority to hand it one.
+fn write_self_signed_cert(cert_path: &Path, key_path: &Path) -> Result<()> {
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()])
+        .context("Failed to generate a self-signed cert")?;
+
+    std::fs::write(cert_path, cert.serialize_pem().context("Failed to serialize the cert")?)
+        .context("Failed to write the test TLS cert")?;
+    std::fs::write(key_path, cert.serialize_private_key_pem())
+        .context("Failed to write the test TLS key")?;
+
+    Ok(())
+}
+
+/// A reqwest client that trusts the daemon's self-signed test cert instead of a real CA, since
+/// every test daemon mints its own.
+pub fn web_status_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .build()
+        .expect("Failed to build the test web status client")
+}
+
+/// Fetch the current status from a daemon's read-only HTTP status API and parse it as JSON.
+/// Useful for asserting that it matches the `Message::Status` response from the socket.
+pub async fn fetch_web_status(daemon: &PueueDaemon) -> Result<serde_json::Value> {
+    let port = wait_for_web_port(daemon).await?;
+    let url = format!("https://localhost:{port}/status");
+    let secret = web_status_secret(daemon)?;
+
+    web_status_client()
+        .get(url)
+        .bearer_auth(secret.trim())
+        .send()
+        .await
+        .context("Failed to reach the web status API")?
+        .json()
+        .await
+        .context("Failed to parse the web status API response as JSON")
+}
+
+/// Read the shared secret a test daemon's web status API was configured with.
+pub fn web_status_secret(daemon: &PueueDaemon) -> Result<String> {
+    daemon
+        .settings
+        .shared
+        .shared_secret_path
+        .as_ref()
+        .map(std::fs::read_to_string)
+        .transpose()
+        .context("Failed to read the web status API's shared secret")
+        .map(|secret| secret.unwrap_or_default())
+}
+
+/// Wait for the daemon to record the port its web status API actually bound to (it was
+/// configured with an ephemeral `web_port: Some(0)`, so the real port is only known once the
+/// listener comes up) and return it.
+pub async fn wait_for_web_port(daemon: &PueueDaemon) -> Result<u16> {
+    let port_file = daemon.settings.shared.pueue_directory().join("web.port");
+
+    for _ in 0..40 {
+        if let Ok(content) = std::fs::read_to_string(&port_file) {
+            if let Ok(port) = content.trim().parse() {
+                return Ok(port);
+            }
+        }
+        sleep_ms(50).await;
+    }
+
+    bail!("The web status API never recorded its port after 2sec")
+}
+
+/// Poll every task log file under `daemon`'s pueue_directory until one of them contains
+/// `needle`, or bail out after a couple of seconds. Useful for asserting on a task's output
+/// without needing to know its exact task id.
+pub async fn wait_for_log_containing(daemon: &PueueDaemon, needle: &str) -> Result<()> {
+    let log_dir = daemon.settings.shared.pueue_directory();
+
+    for _ in 0..40 {
+        if let Ok(entries) = std::fs::read_dir(&log_dir) {
+            for entry in entries.flatten() {
+                if let Ok(content) = std::fs::read_to_string(entry.path()) {
+                    if content.contains(needle) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        sleep_ms(50).await;
+    }
+
+    bail!("No task log contained {needle:?} after 2sec")
+}
+
+/// Create a few test groups, all bound to the default local backend. `daemon()`/`daemons()` set
+/// these up on every test daemon, so most tests can add tasks to a non-default group without
+/// having to create one themselves first.
 pub async fn create_test_groups(shared: &Shared) -> Result<()> {
-    add_group_with_slots(shared, "test_2", 2).await?;
-    add_group_with_slots(shared, "test_3", 3).await?;
-    add_group_with_slots(shared, "test_5", 5).await?;
+    add_group_with_backend(shared, "test_2", "local").await?;
+    add_group_with_backend(shared, "test_3", "local").await?;
+    add_group_with_backend(shared, "test_5", "local").await?;
+
+    Ok(())
+}
+
+/// Sleep for `ms` milliseconds. Thin wrapper around [tokio::time::sleep] so the many polling
+/// loops in this module read as plain integers instead of repeating `Duration::from_millis`.
+async fn sleep_ms(ms: u64) {
+    tokio::time::sleep(std::time::Duration::from_millis(ms)).await;
+}
 
-    wait_for_group(shared, "test_3").await?;
-    wait_for_group(shared, "test_5").await?;
+/// Create a test group that's configured to run its tasks through a custom execution backend
+/// (e.g. `ssh host --` or `docker exec`) instead of as local child processes of the daemon.
+pub async fn create_test_group_with_backend(
+    shared: &Shared,
+    group: &str,
+    backend: &str,
+) -> Result<()> {
+    // `add_group_with_backend` already waits for the daemon's `Success` response before
+    // returning, so the group is guaranteed to exist by the time this returns.
+    add_group_with_backend(shared, group, backend).await?;
 
     Ok(())
 }
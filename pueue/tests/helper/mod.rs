@@ -0,0 +1,12 @@
+// This module is compiled separately into every integration test binary under `tests/`, each of
+// which only exercises a subset of it; clippy's dead-code/unused-import lints fire per binary
+// rather than across the whole `tests/` tree, so they'd otherwise flag whatever a given test
+// happens not to use.
+#![allow(dead_code, unused_imports)]
+
+pub mod factories;
+pub mod fixtures;
+
+pub use factories::*;
+pub use fixtures::*;
+pub use pueue_lib::network::message::{get_pid, send_message};
@@ -0,0 +1,23 @@
+mod helper;
+
+use helper::*;
+
+/// A client talking to a [pueue_lib::manager::Manager] should see the task lists of every
+/// daemon it manages merged together, keyed by daemon name.
+#[tokio::test]
+async fn status_merges_task_lists_from_two_daemons() {
+    let (instances, manager) = daemons(2).await.unwrap();
+
+    add_task(&instances[0].settings.shared, "true", false)
+        .await
+        .unwrap();
+    add_task(&instances[1].settings.shared, "true", false)
+        .await
+        .unwrap();
+
+    let status = manager.status().await.unwrap();
+
+    assert_eq!(status.len(), 2);
+    assert_eq!(status["daemon-0"].len(), 1);
+    assert_eq!(status["daemon-1"].len(), 1);
+}
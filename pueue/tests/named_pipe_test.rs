@@ -0,0 +1,22 @@
+#![cfg(windows)]
+
+mod helper;
+
+use helper::*;
+use pueue_lib::network::message::{get_pid, send_message, Message};
+
+/// Round-trip a message over the Windows named-pipe transport. Every other test in this crate
+/// exercises this same `send_message`/`get_pid` path indirectly, but none of them are
+/// windows-gated, so a regression specific to the named-pipe transport could otherwise slip
+/// through unnoticed on Windows CI.
+#[tokio::test]
+async fn round_trips_a_message_over_the_named_pipe() {
+    let daemon = daemon().await.unwrap();
+    let shared = &daemon.settings.shared;
+
+    let pid = get_pid(&shared.pid_path()).await.unwrap();
+    assert_eq!(pid, daemon.pid);
+
+    let response = send_message(shared, Message::Status).await.unwrap();
+    assert!(matches!(response, Message::StatusResponse(_)));
+}
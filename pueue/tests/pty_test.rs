@@ -0,0 +1,35 @@
+mod helper;
+
+use helper::*;
+
+/// A task added with `pty: true` should see a real terminal on stdout, just like it would when
+/// run interactively, instead of the plain pipe it gets by default.
+#[tokio::test]
+async fn pty_task_reports_a_real_terminal() {
+    let daemon = daemon().await.unwrap();
+
+    add_pty_task(
+        &daemon.settings.shared,
+        "if [ -t 1 ]; then echo IS_A_TTY; else echo NOT_A_TTY; fi",
+    )
+    .await
+    .unwrap();
+
+    wait_for_log_containing(&daemon, "IS_A_TTY").await.unwrap();
+}
+
+/// A task added with both `pty: true` and an `stdin` payload should see that payload on its
+/// child's stdin, the same way a plain piped task would, instead of the payload being silently
+/// dropped because the pty path doesn't read `add.stdin`.
+#[tokio::test]
+async fn pty_task_receives_its_stdin_payload() {
+    let daemon = daemon().await.unwrap();
+
+    add_pty_task_with_stdin(&daemon.settings.shared, "cat", "hello from pty stdin")
+        .await
+        .unwrap();
+
+    wait_for_log_containing(&daemon, "hello from pty stdin")
+        .await
+        .unwrap();
+}
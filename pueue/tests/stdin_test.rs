@@ -0,0 +1,18 @@
+mod helper;
+
+use helper::*;
+
+/// A task added with an `stdin` payload should see that payload on its child's stdin, even for
+/// a plain pipe-through command like `cat`.
+#[tokio::test]
+async fn stdin_payload_is_piped_into_the_task() {
+    let daemon = daemon().await.unwrap();
+
+    add_task_with_stdin(&daemon.settings.shared, "cat", "hello from stdin")
+        .await
+        .unwrap();
+
+    wait_for_log_containing(&daemon, "hello from stdin")
+        .await
+        .unwrap();
+}
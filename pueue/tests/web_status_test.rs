@@ -0,0 +1,181 @@
+mod helper;
+
+use std::sync::Arc;
+
+use base64::Engine;
+use sha1::{Digest, Sha1};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls;
+
+use helper::*;
+use pueue_lib::network::message::{send_message, Message, TaskStatus};
+
+/// The JSON the web status API serves should match what `send_message(Message::Status)` returns
+/// over the regular socket.
+#[tokio::test]
+async fn web_status_matches_socket_status() {
+    let daemon = daemon().await.unwrap();
+    let shared = &daemon.settings.shared;
+
+    add_task(shared, "true", false).await.unwrap();
+
+    let socket_response = send_message(shared, Message::Status).await.unwrap();
+    let Message::StatusResponse(socket_tasks) = socket_response else {
+        panic!("Expected a StatusResponse from the socket");
+    };
+
+    let web_status = fetch_web_status(&daemon).await.unwrap();
+    let web_tasks: Vec<TaskStatus> = serde_json::from_value(web_status).unwrap();
+
+    assert_eq!(socket_tasks, web_tasks);
+}
+
+/// A request that doesn't present the daemon's shared secret must be rejected, not served the
+/// same task data an authenticated client would get.
+#[tokio::test]
+async fn web_status_rejects_requests_without_the_shared_secret() {
+    let daemon = daemon().await.unwrap();
+    let port = wait_for_web_port(&daemon).await.unwrap();
+
+    let response = web_status_client()
+        .get(format!("https://localhost:{port}/status"))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+}
+
+/// The bundled dashboard at `/` should be servable without the shared secret, since it's just
+/// static markup; only `/status` and `/ws` actually hand out task data.
+#[tokio::test]
+async fn web_status_serves_the_bundled_dashboard() {
+    let daemon = daemon().await.unwrap();
+    let port = wait_for_web_port(&daemon).await.unwrap();
+
+    let response = web_status_client()
+        .get(format!("https://localhost:{port}/"))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let body = response.text().await.unwrap();
+    assert!(body.contains("pueue status"));
+}
+
+/// A verifier that trusts any server cert, since every test daemon mints its own self-signed one
+/// and there's no real CA to check it against.
+#[derive(Debug)]
+struct AcceptAnyCert;
+
+impl rustls::client::ServerCertVerifier for AcceptAnyCert {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &rustls::Certificate,
+        _intermediates: &[rustls::Certificate],
+        _server_name: &rustls::ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: std::time::SystemTime,
+    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::ServerCertVerified::assertion())
+    }
+}
+
+/// `/ws` should push a fresh status snapshot as soon as a task is added, without the client
+/// having to poll `/status` again.
+#[tokio::test]
+async fn web_status_pushes_updates_over_the_websocket() {
+    let daemon = daemon().await.unwrap();
+    let port = wait_for_web_port(&daemon).await.unwrap();
+    let secret = web_status_secret(&daemon).unwrap();
+
+    let tls_config = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_custom_certificate_verifier(Arc::new(AcceptAnyCert))
+        .with_no_client_auth();
+    let connector = tokio_rustls::TlsConnector::from(Arc::new(tls_config));
+
+    let tcp = TcpStream::connect(("127.0.0.1", port)).await.unwrap();
+    let server_name = rustls::ServerName::try_from("localhost").unwrap();
+    let mut stream = connector.connect(server_name, tcp).await.unwrap();
+
+    let mut key_bytes = [0u8; 16];
+    getrandom(&mut key_bytes);
+    let key = base64::engine::general_purpose::STANDARD.encode(key_bytes);
+    let request = format!(
+        "GET /ws?token={secret} HTTP/1.1\r\n\
+         Host: localhost\r\n\
+         Connection: Upgrade\r\n\
+         Upgrade: websocket\r\n\
+         Sec-WebSocket-Version: 13\r\n\
+         Sec-WebSocket-Key: {key}\r\n\r\n"
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let expected_accept = {
+        let mut hasher = Sha1::new();
+        hasher.update(key.as_bytes());
+        hasher.update(b"258EAFA5-E914-47DA-95CA-C5AB0DC85B11");
+        base64::engine::general_purpose::STANDARD.encode(hasher.finalize())
+    };
+
+    let handshake = read_until_blank_line(&mut stream).await;
+    assert!(handshake.contains("101"));
+    assert!(handshake.contains(&expected_accept));
+
+    // First push: the empty task list from right after connecting.
+    let first = read_text_frame(&mut stream).await;
+    let first_tasks: Vec<TaskStatus> = serde_json::from_slice(&first).unwrap();
+    assert!(first_tasks.is_empty());
+
+    // Adding a task should trigger a second push with the new task in it.
+    add_task(&daemon.settings.shared, "true", false).await.unwrap();
+    let second = read_text_frame(&mut stream).await;
+    let second_tasks: Vec<TaskStatus> = serde_json::from_slice(&second).unwrap();
+    assert_eq!(second_tasks.len(), 1);
+}
+
+/// Fill `buf` with bytes unique enough for a `Sec-WebSocket-Key` test fixture, without pulling in
+/// a `rand` dependency just for this: `RandomState` already seeds itself from the OS per call.
+fn getrandom(buf: &mut [u8]) {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    for chunk in buf.chunks_mut(8) {
+        let value = RandomState::new().build_hasher().finish();
+        let bytes = value.to_be_bytes();
+        chunk.copy_from_slice(&bytes[..chunk.len()]);
+    }
+}
+
+async fn read_until_blank_line<S: tokio::io::AsyncRead + Unpin>(stream: &mut S) -> String {
+    let mut buf = Vec::new();
+    let mut byte = [0u8; 1];
+    loop {
+        stream.read_exact(&mut byte).await.unwrap();
+        buf.push(byte[0]);
+        if buf.ends_with(b"\r\n\r\n") {
+            break;
+        }
+    }
+    String::from_utf8_lossy(&buf).into_owned()
+}
+
+/// Read one unmasked server->client text frame and return its payload.
+async fn read_text_frame<S: tokio::io::AsyncRead + Unpin>(stream: &mut S) -> Vec<u8> {
+    let mut header = [0u8; 2];
+    stream.read_exact(&mut header).await.unwrap();
+    let mut len = (header[1] & 0x7f) as usize;
+    if len == 126 {
+        let mut ext = [0u8; 2];
+        stream.read_exact(&mut ext).await.unwrap();
+        len = u16::from_be_bytes(ext) as usize;
+    }
+
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload).await.unwrap();
+    payload
+}
@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Wraps a task's command so it actually runs somewhere other than as a local child process of
+/// the daemon, e.g. over ssh or inside a container.
+pub trait CommandRunner {
+    /// Return the command that should actually be spawned, with `command` embedded in it.
+    ///
+    /// The result is always executed via a *local* `sh -c`, so implementations that hand
+    /// `command` off to a shell on the other side of some boundary (another host, a container)
+    /// must single-quote-escape it into one opaque argument first. Otherwise shell metacharacters
+    /// in `command` (`&&`, `;`, `$()`, ...) get parsed by the local shell before the remote one
+    /// ever sees them. `envs` must be spliced into that same quoted argument too, since they're
+    /// only set on the local `sh -c` process and don't cross the boundary on their own.
+    fn wrap_command(&self, command: &str, envs: &HashMap<String, String>) -> String;
+}
+
+/// Single-quote `value` so it survives as one opaque word through a local `sh -c`, escaping any
+/// embedded single quotes along the way.
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+/// Prefix `command` with `export`s for `envs`, so a shell on the other side of a remote/container
+/// boundary sees them even though they were only ever set as real env vars on the local process.
+fn with_env_prefix(command: &str, envs: &HashMap<String, String>) -> String {
+    if envs.is_empty() {
+        return command.to_string();
+    }
+
+    let mut keys: Vec<&String> = envs.keys().collect();
+    keys.sort();
+    let exports = keys
+        .into_iter()
+        .map(|key| format!("{key}={}", shell_quote(&envs[key])))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    format!("export {exports}; {command}")
+}
+
+/// Where a group's tasks are executed. Selectable per group, so a single daemon can run some
+/// tasks locally and others remotely or in a container.
+#[derive(PartialEq, Eq, Clone, Debug, Default, Serialize, Deserialize)]
+pub enum Backend {
+    /// Run the command as a local child process of the daemon. The default.
+    #[default]
+    Local,
+    /// Run the command on a remote host via `ssh <host> -- <command>`.
+    Ssh { host: String },
+    /// Run the command inside a running container via `docker exec <container> sh -c <command>`.
+    Docker { container: String },
+    /// Run the command by substituting it into an arbitrary `{command}` template, e.g.
+    /// `"firejail -- {command}"`.
+    Template { template: String },
+}
+
+impl CommandRunner for Backend {
+    fn wrap_command(&self, command: &str, envs: &HashMap<String, String>) -> String {
+        match self {
+            // Runs as a real local child process, so its real env vars (set separately by the
+            // caller) already reach it; no need to splice anything into the command text.
+            Backend::Local => command.to_string(),
+            Backend::Ssh { host } => {
+                format!(
+                    "ssh {} -- {}",
+                    shell_quote(host),
+                    shell_quote(&with_env_prefix(command, envs))
+                )
+            }
+            Backend::Docker { container } => {
+                format!(
+                    "docker exec {} sh -c {}",
+                    shell_quote(container),
+                    shell_quote(&with_env_prefix(command, envs))
+                )
+            }
+            Backend::Template { template } => {
+                template.replace("{command}", &shell_quote(&with_env_prefix(command, envs)))
+            }
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod backend;
+pub mod manager;
+pub mod network;
+pub mod settings;
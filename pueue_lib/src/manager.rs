@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+
+use crate::network::message::{Message, TaskStatus};
+use crate::network::protocol::send_message;
+use crate::settings::Shared;
+
+/// A registry of named daemon connections, so a single client can look up a specific daemon by
+/// name ([Manager::resolve]) or aggregate a response across all of them ([Manager::status]).
+/// Nothing in this crate exposes a command-line option for picking a daemon by name.
+#[derive(Default, Clone, Debug)]
+pub struct Manager {
+    daemons: HashMap<String, Shared>,
+}
+
+impl Manager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a daemon under `name`, so it can be resolved by [Manager::resolve] or included
+    /// in an aggregate call like [Manager::status].
+    pub fn add_daemon(&mut self, name: impl Into<String>, shared: Shared) {
+        self.daemons.insert(name.into(), shared);
+    }
+
+    /// Resolve a daemon name to its connection settings.
+    pub fn resolve(&self, name: &str) -> Result<&Shared> {
+        self.daemons
+            .get(name)
+            .with_context(|| format!("No daemon named {name:?} is registered with this manager"))
+    }
+
+    /// Names of all daemons currently registered with this manager.
+    pub fn daemon_names(&self) -> impl Iterator<Item = &str> {
+        self.daemons.keys().map(String::as_str)
+    }
+
+    /// Fetch the task status from every registered daemon and merge them into a single map,
+    /// keyed by daemon name.
+    pub async fn status(&self) -> Result<HashMap<String, Vec<TaskStatus>>> {
+        let mut merged = HashMap::new();
+
+        for (name, shared) in &self.daemons {
+            let response = send_message(shared, Message::Status)
+                .await
+                .with_context(|| format!("Failed to fetch status from daemon {name:?}"))?;
+
+            let Message::StatusResponse(tasks) = response else {
+                anyhow::bail!("Daemon {name:?} sent an unexpected response to Message::Status");
+            };
+
+            merged.insert(name.clone(), tasks);
+        }
+
+        Ok(merged)
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backend::Backend;
+use crate::settings::Shared;
+
+// Re-exported here so callers can `use pueue_lib::network::message::*;` and get both the
+// message types and the functions that send them, without needing to know about the protocol
+// module that implements the wire format.
+pub use crate::network::protocol::{get_pid, send_message};
+
+/// Sent by the client to ask the daemon to enqueue a new task.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct AddMessage {
+    pub command: String,
+    pub path: PathBuf,
+    pub envs: HashMap<String, String>,
+    pub start_immediately: bool,
+    pub stashed: bool,
+    pub group: String,
+    pub dependencies: Vec<usize>,
+    pub priority: Option<i32>,
+    pub label: Option<String>,
+    /// Allocate a pseudo-terminal for the spawned child instead of plain piped stdio, so
+    /// programs that check `isatty` behave as they would in an interactive shell.
+    pub pty: bool,
+    /// Payload to write to the child's stdin once it's spawned. With piped stdio the pipe is
+    /// closed right after the payload; with a pty the payload is followed by a single Ctrl-D
+    /// (EOT) instead. The write runs on its own thread, alongside the daemon waiting on the child.
+    pub stdin: Option<String>,
+}
+
+/// Resize the pty of a running task. Only has an effect on tasks that were added with `pty: true`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct PtyResizeMessage {
+    pub task_id: usize,
+    pub width: u16,
+    pub height: u16,
+}
+
+/// Sent by the client to create a new group, optionally bound to a custom execution [Backend].
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct AddGroupMessage {
+    pub name: String,
+    pub backend: Backend,
+}
+
+/// A single task's status, as reported by a daemon in response to [Message::Status].
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct TaskStatus {
+    pub task_id: usize,
+    pub command: String,
+    pub group: String,
+}
+
+/// All messages that can be sent between the client and the daemon.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub enum Message {
+    Add(AddMessage),
+    AddGroup(AddGroupMessage),
+    PtyResize(PtyResizeMessage),
+    /// Ask the daemon to report the status of every task it currently knows about.
+    Status,
+    StatusResponse(Vec<TaskStatus>),
+    Success(String),
+    Failure(String),
+}
+
+impl From<AddMessage> for Message {
+    fn from(message: AddMessage) -> Self {
+        Message::Add(message)
+    }
+}
+
+impl From<AddGroupMessage> for Message {
+    fn from(message: AddGroupMessage) -> Self {
+        Message::AddGroup(message)
+    }
+}
+
+impl From<PtyResizeMessage> for Message {
+    fn from(message: PtyResizeMessage) -> Self {
+        Message::PtyResize(message)
+    }
+}
+
+/// Build the default [AddMessage] for a given command, using the current working directory and
+/// the `default` group. Callers usually tweak a few fields before sending it off.
+pub fn create_add_message(_shared: &Shared, command: &str) -> AddMessage {
+    AddMessage {
+        command: command.to_string(),
+        path: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+        envs: HashMap::new(),
+        start_immediately: false,
+        stashed: false,
+        group: "default".to_string(),
+        dependencies: Vec::new(),
+        priority: None,
+        label: None,
+        pty: false,
+        stdin: None,
+    }
+}
@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::network::message::Message;
+use crate::settings::Shared;
+
+/// Read the daemon's pid from its pid file.
+pub async fn get_pid(pid_path: &Path) -> Result<i32> {
+    let content = tokio::fs::read_to_string(pid_path)
+        .await
+        .context("Failed to read the daemon's pid file")?;
+
+    content
+        .trim()
+        .parse()
+        .context("Pid file didn't contain a valid pid")
+}
+
+/// Send a message to the daemon behind `shared` and wait for its response.
+/// Messages are encoded as a single line of JSON, terminated by `\n`.
+#[cfg(not(target_os = "windows"))]
+pub async fn send_message(shared: &Shared, message: impl Into<Message>) -> Result<Message> {
+    let mut stream = tokio::net::UnixStream::connect(shared.unix_socket_path())
+        .await
+        .context("Failed to connect to the daemon's unix socket")?;
+
+    send(&mut stream, &message.into()).await?;
+    receive(&mut stream).await
+}
+
+/// Send a message to the daemon behind `shared` and wait for its response, using the Windows
+/// named-pipe transport instead of a unix socket.
+#[cfg(target_os = "windows")]
+pub async fn send_message(shared: &Shared, message: impl Into<Message>) -> Result<Message> {
+    let mut stream = tokio::net::windows::named_pipe::ClientOptions::new()
+        .open(shared.named_pipe_path())
+        .context("Failed to connect to the daemon's named pipe")?;
+
+    send(&mut stream, &message.into()).await?;
+    receive(&mut stream).await
+}
+
+async fn send(
+    stream: &mut (impl AsyncWriteExt + Unpin),
+    message: &Message,
+) -> Result<()> {
+    let mut payload = serde_json::to_vec(message).context("Failed to serialize message")?;
+    payload.push(b'\n');
+
+    stream
+        .write_all(&payload)
+        .await
+        .context("Failed to send message to the daemon")
+}
+
+async fn receive(stream: &mut (impl AsyncReadExt + Unpin)) -> Result<Message> {
+    let mut buf = Vec::new();
+    let mut byte = [0u8; 1];
+    loop {
+        let read = stream
+            .read(&mut byte)
+            .await
+            .context("Failed to read the daemon's response")?;
+        if read == 0 || byte[0] == b'\n' {
+            break;
+        }
+        buf.push(byte[0]);
+    }
+
+    serde_json::from_slice(&buf).context("Failed to parse the daemon's response")
+}
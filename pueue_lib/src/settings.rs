@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Configuration that's shared between the daemon and the client.
+/// It describes how to reach a specific daemon instance: which transport to use, where its
+/// TLS material lives and which runtime paths it uses.
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct Shared {
+    pub pueue_directory: Option<PathBuf>,
+    pub runtime_directory: Option<PathBuf>,
+    pub alias_file: Option<PathBuf>,
+
+    #[cfg(not(target_os = "windows"))]
+    pub use_unix_socket: bool,
+    #[cfg(not(target_os = "windows"))]
+    pub unix_socket_path: Option<PathBuf>,
+
+    /// Name of the Windows named pipe used as the local IPC transport, e.g.
+    /// `\\.\pipe\pueue-<hash>`. Unlike the unix socket, this isn't backed by a filesystem path.
+    #[cfg(target_os = "windows")]
+    pub named_pipe_path: Option<String>,
+
+    pub pid_path: Option<PathBuf>,
+    pub host: String,
+    pub port: String,
+    pub daemon_cert: Option<PathBuf>,
+    pub daemon_key: Option<PathBuf>,
+    pub shared_secret_path: Option<PathBuf>,
+
+    /// Port the read-only HTTP status API listens on, if enabled at all. `Some(0)` binds an
+    /// ephemeral port; the daemon records the port it actually bound in `web.port` inside the
+    /// pueue directory, since it may differ from the configured one.
+    pub web_port: Option<u16>,
+}
+
+impl Shared {
+    /// The directory pueue uses to store its task logs, pid file, etc.
+    pub fn pueue_directory(&self) -> PathBuf {
+        self.pueue_directory
+            .clone()
+            .unwrap_or_else(|| std::env::temp_dir().join("pueue"))
+    }
+
+    /// Directory used for ephemeral runtime files, e.g. the unix socket or named pipe.
+    pub fn runtime_directory(&self) -> PathBuf {
+        self.runtime_directory
+            .clone()
+            .unwrap_or_else(|| self.pueue_directory())
+    }
+
+    /// Path of the unix socket used for local IPC on non-Windows platforms.
+    #[cfg(not(target_os = "windows"))]
+    pub fn unix_socket_path(&self) -> PathBuf {
+        self.unix_socket_path
+            .clone()
+            .unwrap_or_else(|| self.runtime_directory().join("pueue.socket"))
+    }
+
+    /// Name of the named pipe used for local IPC on Windows, derived from the runtime directory
+    /// if one wasn't explicitly configured.
+    #[cfg(target_os = "windows")]
+    pub fn named_pipe_path(&self) -> String {
+        self.named_pipe_path.clone().unwrap_or_else(|| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            std::hash::Hash::hash(&self.runtime_directory(), &mut hasher);
+            format!("\\\\.\\pipe\\pueue-{:x}", std::hash::Hasher::finish(&hasher))
+        })
+    }
+
+    /// Path of the daemon's pid file.
+    pub fn pid_path(&self) -> PathBuf {
+        self.pid_path
+            .clone()
+            .unwrap_or_else(|| self.pueue_directory().join("pueue.pid"))
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct Client {
+    pub restart_in_place: bool,
+    pub read_local_logs: bool,
+    pub show_confirmation_questions: bool,
+    pub show_expanded_aliases: bool,
+    pub dark_mode: bool,
+    pub max_status_lines: Option<usize>,
+    pub status_time_format: String,
+    pub status_datetime_format: String,
+}
+
+#[derive(PartialEq, Eq, Clone, Debug, Serialize, Deserialize)]
+pub struct Daemon {
+    pub pause_group_on_failure: bool,
+    pub pause_all_on_failure: bool,
+    pub callback: Option<String>,
+    pub callback_log_lines: usize,
+    /// Parallel-slot configuration used to be declared statically in the config file.
+    /// Groups are now managed at runtime via the `group` subcommand; this field only exists
+    /// so older config files can still be parsed during migration.
+    #[deprecated(note = "Groups are now managed at runtime via the `group` subcommand")]
+    pub groups: Option<HashMap<String, usize>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Settings {
+    pub client: Client,
+    pub daemon: Daemon,
+    pub shared: Shared,
+    /// Named, partial config overrides, keyed by profile name. `pueued` parses a `--profile`
+    /// flag, but `daemon::run` ignores it, so none of these overrides is applied.
+    pub profiles: HashMap<String, serde_yaml::Value>,
+}
+
+impl Settings {
+    /// Serialize these settings and write them to `path`.
+    pub fn save(&self, path: &Option<PathBuf>) -> Result<()> {
+        let config_path = path
+            .clone()
+            .context("Tried to save settings without a config path")?;
+        let content = serde_yaml::to_string(self).context("Failed to serialize settings")?;
+        std::fs::write(&config_path, content)
+            .context("Failed to write settings to the config file")?;
+
+        Ok(())
+    }
+}